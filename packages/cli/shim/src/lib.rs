@@ -3,29 +3,64 @@
 //! This shim translates simple (ptr, len) pairs from C into proper
 //! sys::Buffer descriptors that the Calimero runtime expects.
 
+use core::sync::atomic::{AtomicU64, Ordering};
+
 use calimero_sdk::env;
-use calimero_sys::{self as sys, Bool, Buffer, Event, PtrSizedInt, RegisterId, Ref};
+use calimero_sys::{self as sys, Bool, Buffer, Event, Location, PtrSizedInt, RegisterId, Ref};
+
+/// Errno-style status codes returned by fallible shim exports, so the QuickJS
+/// bindings can throw a precise JS exception instead of inspecting an opaque
+/// boolean.
+#[repr(i32)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShimStatus {
+    Ok = 0,
+    NotFound = 1,
+    RegisterTooSmall = 2,
+    InvalidUtf8 = 3,
+    BadFd = 5,
+    BufferOverrun = 6,
+    Internal = 7,
+    FuelExhausted = 8,
+}
 
-// Helper to convert Bool to u32
+/// Maps a `Bool` host-call result to a `ShimStatus`, treating an opaque
+/// non-bool payload as an internal error.
 #[inline]
-fn bool_to_u32(b: Bool) -> u32 {
+fn status_from_bool(b: Bool, on_false: ShimStatus) -> ShimStatus {
     match b.try_into() {
-        Ok(true) => 1,
-        Ok(false) => 0,
-        Err(x) => x,
+        Ok(true) => ShimStatus::Ok,
+        Ok(false) => on_false,
+        Err(_) => ShimStatus::Internal,
     }
 }
 
-/// Helper to create a Buffer from raw pointer and length
+/// Helper to create a mutable Buffer from raw pointer and length
 #[inline]
-unsafe fn buffer_from_raw(ptr: u64, len: u64) -> Buffer<'static> {
-    Buffer::from(core::slice::from_raw_parts(ptr as *const u8, len as usize))
+unsafe fn buffer_mut_from_raw(ptr: u64, len: u64) -> Buffer<'static> {
+    Buffer::from(core::slice::from_raw_parts_mut(ptr as *mut u8, len as usize))
 }
 
-/// Helper to create a mutable Buffer from raw pointer and length  
+/// Reinterprets a raw `(ptr, len)` pair as a `&str`.
+///
+/// With the `strict-utf8` feature enabled this validates the bytes and
+/// returns `InvalidUtf8` on failure, which is what production builds should
+/// opt into. Without it, this keeps the zero-cost `from_utf8_unchecked`
+/// conversion benchmarks rely on, so a non-UTF-8 byte range is undefined
+/// behavior in that mode.
 #[inline]
-unsafe fn buffer_mut_from_raw(ptr: u64, len: u64) -> Buffer<'static> {
-    Buffer::from(core::slice::from_raw_parts_mut(ptr as *mut u8, len as usize))
+unsafe fn str_from_raw(ptr: u64, len: u64) -> Result<&'static str, ShimStatus> {
+    let bytes = core::slice::from_raw_parts(ptr as *const u8, len as usize);
+
+    #[cfg(feature = "strict-utf8")]
+    {
+        core::str::from_utf8(bytes).map_err(|_| ShimStatus::InvalidUtf8)
+    }
+
+    #[cfg(not(feature = "strict-utf8"))]
+    {
+        Ok(core::str::from_utf8_unchecked(bytes))
+    }
 }
 
 // ===========================
@@ -33,25 +68,64 @@ unsafe fn buffer_mut_from_raw(ptr: u64, len: u64) -> Buffer<'static> {
 // ===========================
 
 #[no_mangle]
-pub extern "C" fn shim_log_utf8(ptr: u64, len: u64) {
-    let message = unsafe {
-        core::str::from_utf8_unchecked(core::slice::from_raw_parts(ptr as *const u8, len as usize))
+pub extern "C" fn shim_log_utf8(ptr: u64, len: u64) -> ShimStatus {
+    let message = match unsafe { str_from_raw(ptr, len) } {
+        Ok(message) => message,
+        Err(status) => return status,
     };
     env::log(message);
+    ShimStatus::Ok
+}
+
+// ===========================
+// Panic / Abort
+// ===========================
+
+/// Aborts execution with a diagnostic message, mirroring the `file:line: message`
+/// shape of a native Rust panic. The QuickJS glue installs this as the engine's
+/// uncaught-exception and out-of-memory handler so a thrown JS `Error` surfaces
+/// as a host-visible abort instead of being logged and swallowed.
+#[no_mangle]
+pub extern "C" fn shim_panic_utf8(
+    msg_ptr: u64,
+    msg_len: u64,
+    file_ptr: u64,
+    file_len: u64,
+    line: u32,
+) -> ! {
+    // Both byte ranges go through `str_from_raw`, like the other shims: with
+    // the `strict-utf8` feature an invalid range is replaced by
+    // "<invalid utf8>", and without it the bytes are reinterpreted unchecked.
+    let message = unsafe { str_from_raw(msg_ptr, msg_len) }.unwrap_or("<invalid utf8>");
+    let file = unsafe { str_from_raw(file_ptr, file_len) }.unwrap_or("<invalid utf8>");
+
+    let formatted = format!("panic at {file}:{line}: {message}");
+
+    // `Location`'s fields are private with no constructor that takes raw
+    // parts, so the engine-supplied file/line can't be threaded into it —
+    // they're embedded in `formatted` above instead, which is what actually
+    // surfaces to the caller. `Location::caller()` only captures this shim's
+    // own source position, not the JS call site, but it's the only `Location`
+    // this crate lets us construct.
+    unsafe { sys::panic_utf8(Ref::new(&Buffer::from(formatted.as_bytes())), Ref::new(&Location::caller())) }
 }
 
 // ===========================
 // Storage
 // ===========================
 
+/// `sys::storage_read` only ever hands back a `Bool`, so a `false` result
+/// is reported as `NotFound` regardless of whether the key was actually
+/// absent or the read failed for some other reason; the current `sys`
+/// surface has no way to tell those two cases apart.
 #[no_mangle]
-pub extern "C" fn shim_storage_read(key_ptr: u64, key_len: u64, register_id: u64) -> u32 {
+pub extern "C" fn shim_storage_read(key_ptr: u64, key_len: u64, register_id: u64) -> ShimStatus {
     let key = unsafe { core::slice::from_raw_parts(key_ptr as *const u8, key_len as usize) };
     let reg_id = RegisterId::new(register_id as usize);
     let result = unsafe {
         sys::storage_read(Ref::new(&Buffer::from(key)), reg_id)
     };
-    bool_to_u32(result)
+    status_from_bool(result, ShimStatus::NotFound)
 }
 
 #[no_mangle]
@@ -61,11 +135,11 @@ pub extern "C" fn shim_storage_write(
     value_ptr: u64,
     value_len: u64,
     register_id: u64,
-) -> u32 {
+) -> ShimStatus {
     let key = unsafe { core::slice::from_raw_parts(key_ptr as *const u8, key_len as usize) };
     let value = unsafe { core::slice::from_raw_parts(value_ptr as *const u8, value_len as usize) };
     let reg_id = RegisterId::new(register_id as usize);
-    
+
     let result = unsafe {
         sys::storage_write(
             Ref::new(&Buffer::from(key)),
@@ -73,18 +147,18 @@ pub extern "C" fn shim_storage_write(
             reg_id,
         )
     };
-    bool_to_u32(result)
+    status_from_bool(result, ShimStatus::Internal)
 }
 
 #[no_mangle]
-pub extern "C" fn shim_storage_remove(key_ptr: u64, key_len: u64, register_id: u64) -> u32 {
+pub extern "C" fn shim_storage_remove(key_ptr: u64, key_len: u64, register_id: u64) -> ShimStatus {
     let key = unsafe { core::slice::from_raw_parts(key_ptr as *const u8, key_len as usize) };
     let reg_id = RegisterId::new(register_id as usize);
-    
+
     let result = unsafe {
         sys::storage_remove(Ref::new(&Buffer::from(key)), reg_id)
     };
-    bool_to_u32(result)
+    status_from_bool(result, ShimStatus::NotFound)
 }
 
 // ===========================
@@ -115,11 +189,16 @@ pub extern "C" fn shim_register_len(register_id: u64) -> u64 {
 }
 
 #[no_mangle]
-pub extern "C" fn shim_read_register(register_id: u64, buf_ptr: u64, buf_len: u64) -> u32 {
+pub extern "C" fn shim_read_register(register_id: u64, buf_ptr: u64, buf_len: u64) -> ShimStatus {
     let reg_id = RegisterId::new(register_id as usize);
+    let needed: PtrSizedInt = unsafe { sys::register_len(reg_id) };
+    if needed.as_usize() as u64 > buf_len {
+        return ShimStatus::RegisterTooSmall;
+    }
+
     let buffer = unsafe { buffer_mut_from_raw(buf_ptr, buf_len) };
     let result = unsafe { sys::read_register(reg_id, Ref::new(&buffer)) };
-    bool_to_u32(result)
+    status_from_bool(result, ShimStatus::NotFound)
 }
 
 // ===========================
@@ -132,17 +211,20 @@ pub extern "C" fn shim_emit(
     kind_len: u64,
     data_ptr: u64,
     data_len: u64,
-) {
-    let kind_bytes = unsafe { core::slice::from_raw_parts(kind_ptr as *const u8, kind_len as usize) };
-    let kind_str = unsafe { core::str::from_utf8_unchecked(kind_bytes) };
+) -> ShimStatus {
+    let kind_str = match unsafe { str_from_raw(kind_ptr, kind_len) } {
+        Ok(kind_str) => kind_str,
+        Err(status) => return status,
+    };
     let data = unsafe { core::slice::from_raw_parts(data_ptr as *const u8, data_len as usize) };
-    
+
     let data_buffer = Buffer::from(data);
     let event = Event::new(kind_str, &data_buffer);
-    
+
     unsafe {
         sys::emit(Ref::new(&event));
     }
+    ShimStatus::Ok
 }
 
 #[no_mangle]
@@ -153,19 +235,22 @@ pub extern "C" fn shim_emit_with_handler(
     data_len: u64,
     handler_ptr: u64,
     handler_len: u64,
-) {
-    let kind_bytes = unsafe { core::slice::from_raw_parts(kind_ptr as *const u8, kind_len as usize) };
-    let kind_str = unsafe { core::str::from_utf8_unchecked(kind_bytes) };
+) -> ShimStatus {
+    let kind_str = match unsafe { str_from_raw(kind_ptr, kind_len) } {
+        Ok(kind_str) => kind_str,
+        Err(status) => return status,
+    };
     let data = unsafe { core::slice::from_raw_parts(data_ptr as *const u8, data_len as usize) };
     let handler = unsafe { core::slice::from_raw_parts(handler_ptr as *const u8, handler_len as usize) };
-    
+
     let data_buffer = Buffer::from(data);
     let handler_buffer = Buffer::from(handler);
     let event = Event::new(kind_str, &data_buffer);
-    
+
     unsafe {
         sys::emit_with_handler(Ref::new(&event), Ref::new(&handler_buffer));
     }
+    ShimStatus::Ok
 }
 
 // ===========================
@@ -197,6 +282,49 @@ pub extern "C" fn shim_time_now(buf_ptr: u64, buf_len: u64) {
     unsafe { sys::time_now(Ref::new(&buffer)) }
 }
 
+// ===========================
+// Fuel
+// ===========================
+//
+// Reproducible gas metering for untrusted contract code, so a runaway JS
+// loop has an in-band stop signal instead of relying on a wall-clock
+// timeout. `calimero_sys` has no host-provided budget primitive yet, so the
+// remaining budget is tracked here in the shim's own state. The engine calls
+// `shim_fuel_set` with the execution's budget before running any guest code,
+// the QuickJS interpreter loop calls `shim_fuel_consume` per N bytecode
+// steps, and the engine treats an underflow's `FuelExhausted` status as a
+// deterministic trap, aborting through the panic shim. Starts at `u64::MAX`
+// so a host that never calls `shim_fuel_set` sees effectively no limit.
+static FUEL_REMAINING: AtomicU64 = AtomicU64::new(u64::MAX);
+
+/// Resets the remaining budget to `budget`, e.g. at the start of each
+/// execution. Not atomic with respect to a concurrent `shim_fuel_consume` on
+/// another thread, but the shim is only ever driven by one QuickJS engine
+/// instance at a time.
+#[no_mangle]
+pub extern "C" fn shim_fuel_set(budget: u64) {
+    FUEL_REMAINING.store(budget, Ordering::Relaxed);
+}
+
+#[no_mangle]
+pub extern "C" fn shim_fuel_remaining() -> u64 {
+    FUEL_REMAINING.load(Ordering::Relaxed)
+}
+
+#[no_mangle]
+pub extern "C" fn shim_fuel_consume(amount: u64) -> ShimStatus {
+    let mut current = FUEL_REMAINING.load(Ordering::Relaxed);
+    loop {
+        let Some(next) = current.checked_sub(amount) else {
+            return ShimStatus::FuelExhausted;
+        };
+        match FUEL_REMAINING.compare_exchange_weak(current, next, Ordering::Relaxed, Ordering::Relaxed) {
+            Ok(_) => return ShimStatus::Ok,
+            Err(observed) => current = observed,
+        }
+    }
+}
+
 // ===========================
 // Blobs
 // ===========================
@@ -231,11 +359,300 @@ pub extern "C" fn shim_blob_write(fd: u64, data_ptr: u64, data_len: u64) -> u64
 }
 
 #[no_mangle]
-pub extern "C" fn shim_blob_close(fd: u64, blob_id_buf_ptr: u64, blob_id_buf_len: u64) -> u32 {
+pub extern "C" fn shim_blob_close(fd: u64, blob_id_buf_ptr: u64, blob_id_buf_len: u64) -> ShimStatus {
     let buffer = unsafe { buffer_mut_from_raw(blob_id_buf_ptr, blob_id_buf_len) };
     let fd_sized: PtrSizedInt = PtrSizedInt::new(fd as usize);
     let result = unsafe { sys::blob_close(fd_sized, Ref::new(&buffer)) };
-    bool_to_u32(result)
+    status_from_bool(result, ShimStatus::BadFd)
+}
+
+// ===========================
+// Host call dispatch
+// ===========================
+//
+// A single tagged entry point that routes capabilities from the
+// per-capability export surface above through one protocol; the wire format
+// carries no version field or version check. Arguments are decoded from a
+// tag-encoded buffer and dispatched through a method table to the matching
+// `sys::*` call, which populates `out_register` the same way the existing
+// per-function shims already do. New host calls can be added to the method
+// table without introducing new `extern "C"` symbols.
+
+/// Wire-format tag preceding each encoded argument field.
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ArgTag {
+    Bytes = 0,
+    U64 = 1,
+    Bool = 2,
+    List = 3,
+}
+
+impl TryFrom<u8> for ArgTag {
+    type Error = ShimStatus;
+
+    fn try_from(tag: u8) -> Result<Self, Self::Error> {
+        match tag {
+            0 => Ok(ArgTag::Bytes),
+            1 => Ok(ArgTag::U64),
+            2 => Ok(ArgTag::Bool),
+            3 => Ok(ArgTag::List),
+            _ => Err(ShimStatus::Internal),
+        }
+    }
+}
+
+/// A decoded host-call field. `List` nests recursively so a single call can
+/// carry, e.g., a vector of keys without a bespoke export per shape.
+#[allow(dead_code, reason = "only METHOD_EMIT/METHOD_STORAGE_* read Bytes so far; U64/Bool/List payloads light up as more methods are wired into dispatch")]
+enum HostArg<'a> {
+    Bytes(&'a [u8]),
+    U64(u64),
+    Bool(bool),
+    List(Vec<HostArg<'a>>),
 }
 
+/// Maximum nesting depth for `List` arguments. Bounds recursion in
+/// `ArgReader::read_arg` so a buffer of deeply-nested single-element lists
+/// can't drive unbounded stack recursion before `args_len` is exhausted.
+const MAX_ARG_DEPTH: usize = 16;
+
+/// Cursor over the length-prefixed, tag-encoded `shim_host_call` buffer.
+struct ArgReader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> ArgReader<'a> {
+    fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes, pos: 0 }
+    }
+
+    fn read_u8(&mut self) -> Result<u8, ShimStatus> {
+        let byte = *self.bytes.get(self.pos).ok_or(ShimStatus::BufferOverrun)?;
+        self.pos += 1;
+        Ok(byte)
+    }
+
+    fn read_u64(&mut self) -> Result<u64, ShimStatus> {
+        let end = self.pos.checked_add(8).ok_or(ShimStatus::BufferOverrun)?;
+        let slice = self.bytes.get(self.pos..end).ok_or(ShimStatus::BufferOverrun)?;
+        self.pos = end;
+        Ok(u64::from_le_bytes(slice.try_into().expect("slice is 8 bytes")))
+    }
+
+    fn read_bytes(&mut self) -> Result<&'a [u8], ShimStatus> {
+        let len = self.read_u64()? as usize;
+        let end = self.pos.checked_add(len).ok_or(ShimStatus::BufferOverrun)?;
+        let slice = self.bytes.get(self.pos..end).ok_or(ShimStatus::BufferOverrun)?;
+        self.pos = end;
+        Ok(slice)
+    }
+
+    fn read_arg(&mut self, depth: usize) -> Result<HostArg<'a>, ShimStatus> {
+        if depth >= MAX_ARG_DEPTH {
+            return Err(ShimStatus::Internal);
+        }
+        match ArgTag::try_from(self.read_u8()?)? {
+            ArgTag::Bytes => Ok(HostArg::Bytes(self.read_bytes()?)),
+            ArgTag::U64 => Ok(HostArg::U64(self.read_u64()?)),
+            ArgTag::Bool => Ok(HostArg::Bool(self.read_u8()? != 0)),
+            ArgTag::List => {
+                let count = self.read_u64()? as usize;
+                // Each item needs at least one tag byte, so a well-formed
+                // count can never exceed the bytes left in the buffer.
+                // Reject it up front instead of reserving capacity off an
+                // attacker-controlled 8-byte field.
+                let remaining = self.bytes.len() - self.pos;
+                if count > remaining {
+                    return Err(ShimStatus::BufferOverrun);
+                }
+                let mut items = Vec::with_capacity(count);
+                for _ in 0..count {
+                    items.push(self.read_arg(depth + 1)?);
+                }
+                Ok(HostArg::List(items))
+            }
+        }
+    }
+
+    fn at_end(&self) -> bool {
+        self.pos == self.bytes.len()
+    }
+}
+
+/// Decodes every field in `bytes` in order; there is no outer list wrapper
+/// since `args_len` already bounds the call's argument list.
+fn decode_args(bytes: &[u8]) -> Result<Vec<HostArg<'_>>, ShimStatus> {
+    let mut reader = ArgReader::new(bytes);
+    let mut args = Vec::new();
+    while !reader.at_end() {
+        args.push(reader.read_arg(0)?);
+    }
+    Ok(args)
+}
+
+// Method table: host-call protocol IDs for the capabilities routed through
+// `shim_host_call`. Add an entry here (and a `dispatch` arm) rather than a
+// new `extern "C"` export when wiring up another capability.
+const METHOD_STORAGE_READ: u32 = 0;
+const METHOD_STORAGE_WRITE: u32 = 1;
+const METHOD_STORAGE_REMOVE: u32 = 2;
+const METHOD_EMIT: u32 = 3;
+
+/// Dispatches a decoded host call to its matching `sys::*` function. Calls
+/// that write a result populate `out_register` themselves, exactly as the
+/// per-function shims above already do.
+fn dispatch(method_id: u32, args: &[HostArg<'_>], out_register: RegisterId) -> Result<ShimStatus, ShimStatus> {
+    match method_id {
+        METHOD_STORAGE_READ => {
+            let [HostArg::Bytes(key)] = args else {
+                return Err(ShimStatus::Internal);
+            };
+            let result = unsafe { sys::storage_read(Ref::new(&Buffer::from(*key)), out_register) };
+            Ok(status_from_bool(result, ShimStatus::NotFound))
+        }
+        METHOD_STORAGE_WRITE => {
+            let [HostArg::Bytes(key), HostArg::Bytes(value)] = args else {
+                return Err(ShimStatus::Internal);
+            };
+            let result = unsafe {
+                sys::storage_write(
+                    Ref::new(&Buffer::from(*key)),
+                    Ref::new(&Buffer::from(*value)),
+                    out_register,
+                )
+            };
+            Ok(status_from_bool(result, ShimStatus::Internal))
+        }
+        METHOD_STORAGE_REMOVE => {
+            let [HostArg::Bytes(key)] = args else {
+                return Err(ShimStatus::Internal);
+            };
+            let result = unsafe { sys::storage_remove(Ref::new(&Buffer::from(*key)), out_register) };
+            Ok(status_from_bool(result, ShimStatus::NotFound))
+        }
+        METHOD_EMIT => {
+            let [HostArg::Bytes(kind), HostArg::Bytes(data)] = args else {
+                return Err(ShimStatus::Internal);
+            };
+            let kind_str = core::str::from_utf8(kind).map_err(|_| ShimStatus::InvalidUtf8)?;
+            let data_buffer = Buffer::from(*data);
+            let event = Event::new(kind_str, &data_buffer);
+            unsafe {
+                sys::emit(Ref::new(&event));
+            }
+            // `emit` has no register output to populate; `out_register` is
+            // simply unused for this method.
+            Ok(ShimStatus::Ok)
+        }
+        _ => Err(ShimStatus::Internal),
+    }
+}
+
+/// Tagged host-call entry point: decodes `args_ptr/args_len` into structured
+/// fields, dispatches on `method_id` through the method table above, and
+/// returns a `ShimStatus` (as `i32`, since this crosses the QuickJS FFI
+/// boundary directly rather than returning the `#[repr(i32)]` enum).
+#[no_mangle]
+pub extern "C" fn shim_host_call(method_id: u32, args_ptr: u64, args_len: u64, out_register: u64) -> i32 {
+    let args_bytes = unsafe { core::slice::from_raw_parts(args_ptr as *const u8, args_len as usize) };
+    let out_register = RegisterId::new(out_register as usize);
+
+    let status = decode_args(args_bytes)
+        .and_then(|args| dispatch(method_id, &args, out_register))
+        .unwrap_or_else(|status| status);
+
+    status as i32
+}
+
+#[cfg(test)]
+mod arg_reader_tests {
+    use super::*;
+
+    fn bytes_field(bytes: &[u8]) -> Vec<u8> {
+        let mut encoded = vec![ArgTag::Bytes as u8];
+        encoded.extend_from_slice(&(bytes.len() as u64).to_le_bytes());
+        encoded.extend_from_slice(bytes);
+        encoded
+    }
+
+    #[test]
+    fn decodes_well_formed_multi_field_buffer() {
+        let mut buf = bytes_field(b"key");
+        buf.push(ArgTag::U64 as u8);
+        buf.extend_from_slice(&42u64.to_le_bytes());
+        buf.push(ArgTag::Bool as u8);
+        buf.push(1);
+
+        let args = decode_args(&buf).expect("well-formed buffer decodes");
+        assert!(matches!(args[0], HostArg::Bytes(b) if b == b"key"));
+        assert!(matches!(args[1], HostArg::U64(42)));
+        assert!(matches!(args[2], HostArg::Bool(true)));
+    }
+
+    #[test]
+    fn rejects_truncated_buffer() {
+        // A `Bytes` tag claiming 10 bytes but only 2 are present.
+        let mut buf = vec![ArgTag::Bytes as u8];
+        buf.extend_from_slice(&10u64.to_le_bytes());
+        buf.extend_from_slice(&[1, 2]);
+
+        assert!(matches!(decode_args(&buf), Err(ShimStatus::BufferOverrun)));
+    }
+
+    #[test]
+    fn rejects_list_count_exceeding_remaining_bytes() {
+        // A `List` tag claiming u64::MAX items with nothing behind it should
+        // be rejected before any allocation, not attempt `Vec::with_capacity`.
+        let mut buf = vec![ArgTag::List as u8];
+        buf.extend_from_slice(&u64::MAX.to_le_bytes());
+
+        assert!(matches!(decode_args(&buf), Err(ShimStatus::BufferOverrun)));
+    }
+
+    #[test]
+    fn rejects_nesting_past_max_arg_depth() {
+        // A chain of single-element `List`s, one level deeper than
+        // `MAX_ARG_DEPTH` allows, each wrapping a final `Bytes` field.
+        let mut buf = Vec::new();
+        for _ in 0..=MAX_ARG_DEPTH {
+            buf.push(ArgTag::List as u8);
+            buf.extend_from_slice(&1u64.to_le_bytes());
+        }
+        buf.extend_from_slice(&bytes_field(b""));
+
+        assert!(matches!(decode_args(&buf), Err(ShimStatus::Internal)));
+    }
+}
+
+#[cfg(test)]
+mod fuel_tests {
+    use std::sync::Mutex;
+
+    use super::*;
+
+    // `FUEL_REMAINING` is one process-wide static; serialize access so tests
+    // running on parallel threads don't observe each other's budget resets.
+    static FUEL_TEST_LOCK: Mutex<()> = Mutex::new(());
+
+    #[test]
+    fn consume_exhausts_exactly_at_budget() {
+        let _guard = FUEL_TEST_LOCK.lock().unwrap();
+        shim_fuel_set(10);
+
+        assert_eq!(shim_fuel_consume(10), ShimStatus::Ok);
+        assert_eq!(shim_fuel_remaining(), 0);
+    }
+
+    #[test]
+    fn consume_one_past_remaining_budget_is_exhausted() {
+        let _guard = FUEL_TEST_LOCK.lock().unwrap();
+        shim_fuel_set(10);
+
+        assert_eq!(shim_fuel_consume(11), ShimStatus::FuelExhausted);
+        // A rejected consume must not have touched the budget.
+        assert_eq!(shim_fuel_remaining(), 10);
+    }
+}
 